@@ -1,22 +1,55 @@
-use crate::api::policy::{AuthorizationPolicy, AuthorizationPolicySpec, Server, ServerSpec};
+// Path-match validation and default-patch encoding below depend on the `regex` and `json_patch`
+// crates respectively; both must be declared as dependencies of this crate's Cargo.toml.
+use crate::api::gateway::{HttpPathMatch, HttpRoute, HttpRouteMatch, HttpRouteRule, HttpRouteSpec};
+use crate::api::policy::{AuthorizationPolicy, AuthorizationPolicySpec, Port, Server, ServerSpec};
 use anyhow::{anyhow, bail, Result};
 use futures::future;
 use hyper::{body::Buf, http, Body, Request, Response};
-use k8s_openapi::serde::de::DeserializeOwned;
+use k8s_openapi::{
+    apimachinery::pkg::apis::meta::v1::{LabelSelector, LabelSelectorRequirement},
+    serde::de::DeserializeOwned,
+};
 use kube::{
     core::{DynamicObject, GroupVersionKind},
     Resource, ResourceExt,
 };
 use linkerd_policy_controller_k8s_index::{Index, SharedIndex};
-use std::task;
+use std::{collections::HashSet, task};
 use thiserror::Error;
 use tracing::{debug, info, warn};
 
 #[derive(Clone)]
 pub struct AdmissionService {
+    /// Used to list resources directly from the API for authoritative admission checks; callers
+    /// must construct this from the same `kube::Client` the controller's startup wires into
+    /// `SharedIndex`'s reflectors.
+    pub client: kube::Client,
     pub index: SharedIndex,
 }
 
+/// Which webhook configuration a request arrived on.
+///
+/// A single `AdmissionService` backs both the validating and the mutating webhook
+/// configurations; the path the API server posts to tells us which one to behave as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum WebhookMode {
+    Validate,
+    Mutate,
+}
+
+/// Maps an incoming request's method and path to the webhook mode it should be served as, or
+/// `None` if the request doesn't match a route we handle.
+///
+/// "/" is kept as an alias for "/validate" so that a `ValidatingWebhookConfiguration` written
+/// against the old, single-path contract keeps working across the rollout.
+fn webhook_mode(method: &http::Method, path: &str) -> Option<WebhookMode> {
+    match (method, path) {
+        (&http::Method::POST, "/" | "/validate") => Some(WebhookMode::Validate),
+        (&http::Method::POST, "/mutate") => Some(WebhookMode::Mutate),
+        _ => None,
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("failed to read request body: {0}")]
@@ -43,15 +76,19 @@ impl hyper::service::Service<Request<Body>> for AdmissionService {
     }
 
     fn call(&mut self, req: Request<Body>) -> Self::Future {
-        if req.method() != http::Method::POST || req.uri().path() != "/" {
-            return Box::pin(future::ok(
-                Response::builder()
-                    .status(http::StatusCode::NOT_FOUND)
-                    .body(Body::empty())
-                    .expect("not found response must be valid"),
-            ));
-        }
+        let mode = match webhook_mode(req.method(), req.uri().path()) {
+            Some(mode) => mode,
+            None => {
+                return Box::pin(future::ok(
+                    Response::builder()
+                        .status(http::StatusCode::NOT_FOUND)
+                        .body(Body::empty())
+                        .expect("not found response must be valid"),
+                ));
+            }
+        };
 
+        let client = self.client.clone();
         let index = self.index.clone();
         Box::pin(async move {
             let bytes = hyper::body::aggregate(req.into_body()).await?;
@@ -66,7 +103,7 @@ impl hyper::service::Service<Request<Body>> for AdmissionService {
             let rsp = match review.try_into() {
                 Ok(req) => {
                     debug!(?req);
-                    admit(req, &index)
+                    admit(req, mode, &client, &index).await
                 }
                 Err(error) => {
                     warn!(%error, "invalid admission request");
@@ -81,7 +118,12 @@ impl hyper::service::Service<Request<Body>> for AdmissionService {
     }
 }
 
-fn admit(req: AdmissionRequest, index: &SharedIndex) -> AdmissionResponse {
+async fn admit(
+    req: AdmissionRequest,
+    mode: WebhookMode,
+    client: &kube::Client,
+    index: &SharedIndex,
+) -> AdmissionResponse {
     let GroupVersionKind {
         group,
         version,
@@ -89,11 +131,24 @@ fn admit(req: AdmissionRequest, index: &SharedIndex) -> AdmissionResponse {
     } = &req.kind;
 
     if *group == *AuthorizationPolicy::group(&()) && *kind == *AuthorizationPolicy::kind(&()) {
-        return admit_authz_policy(req);
+        return match mode {
+            WebhookMode::Validate => admit_authz_policy(req),
+            WebhookMode::Mutate => mutate::<AuthorizationPolicySpec>(req),
+        };
     }
 
     if *group == *Server::group(&()) && kind == &*Server::kind(&()) {
-        return admit_server(req, index);
+        return match mode {
+            WebhookMode::Validate => admit_server(req, client, index).await,
+            WebhookMode::Mutate => mutate::<ServerSpec>(req),
+        };
+    }
+
+    if *group == *HttpRoute::group(&()) && *kind == *HttpRoute::kind(&()) {
+        return match mode {
+            WebhookMode::Validate => admit_httproute(req),
+            WebhookMode::Mutate => mutate::<HttpRouteSpec>(req),
+        };
     }
 
     warn!(%group, %version, %kind, "unsupported resource type");
@@ -136,7 +191,11 @@ fn validate_authz_policy(spec: AuthorizationPolicySpec) -> Result<()> {
 
 // === Server ===
 
-fn admit_server(req: AdmissionRequest, index: &SharedIndex) -> AdmissionResponse {
+async fn admit_server(
+    req: AdmissionRequest,
+    client: &kube::Client,
+    index: &SharedIndex,
+) -> AdmissionResponse {
     let rsp = AdmissionResponse::from(&req);
     let (ns, name, spec) = match parse_spec(req) {
         Ok(s) => s,
@@ -146,7 +205,7 @@ fn admit_server(req: AdmissionRequest, index: &SharedIndex) -> AdmissionResponse
         }
     };
 
-    match validate_server(&ns, &name, spec, &*index.read()) {
+    match validate_server(&ns, &name, spec, client, index).await {
         Ok(()) => rsp,
         Err(error) => {
             info!(%error, %ns, %name, "denying server");
@@ -156,27 +215,385 @@ fn admit_server(req: AdmissionRequest, index: &SharedIndex) -> AdmissionResponse
 }
 
 /// Validates a new server (`review`) against existing `servers`.
-fn validate_server(ns: &str, name: &str, spec: ServerSpec, index: &Index) -> Result<()> {
+///
+/// Existing servers are listed directly from the Kubernetes API so that admission is authoritative
+/// even when the in-memory `index` has not yet observed a racing, concurrently-applied `Server`.
+/// If the API list fails, we fall back to the (possibly stale) index rather than failing open.
+async fn validate_server(
+    ns: &str,
+    name: &str,
+    spec: ServerSpec,
+    client: &kube::Client,
+    index: &SharedIndex,
+) -> Result<()> {
+    let api = kube::Api::<Server>::namespaced(client.clone(), ns);
+    let servers = match api.list(&kube::api::ListParams::default()).await {
+        Ok(servers) => servers,
+        Err(error) => {
+            warn!(%error, %ns, "failed to list servers from the Kubernetes API; falling back to index");
+            return validate_server_against_index(ns, name, spec, &*index.read());
+        }
+    };
+
+    let candidates = servers.iter().map(|srv| {
+        (
+            srv.name_unchecked(),
+            srv.spec.port.clone(),
+            srv.spec.pod_selector.clone(),
+        )
+    });
+    if let Some(conflicting) = conflicting_server(name, &spec.port, &spec.pod_selector, candidates)
+    {
+        bail!("server selector overlaps with existing server {conflicting}");
+    }
+
+    Ok(())
+}
+
+/// Validates a new server against the (possibly stale) in-memory `index`, used as a fallback when
+/// the Kubernetes API is unreachable.
+fn validate_server_against_index(
+    ns: &str,
+    name: &str,
+    spec: ServerSpec,
+    index: &Index,
+) -> Result<()> {
     if let Some(nsidx) = index.get_ns(ns) {
-        for (srvname, srv) in nsidx.servers.iter() {
-            // If the port and pod selectors select the same resources, fail the admission of the
-            // server. Ignore existing instances of this Server (e.g., if the server's metadata is
-            // changing).
-            if *srvname != name
-                // TODO(ver) this isn't rigorous about detecting servers that select the same port if one port
-                // specifies a numeric port and the other specifies the port's name.
-                && *srv.port() == spec.port
-                // TODO(ver) We can probably detect overlapping selectors more effectively.
-                && *srv.pod_selector() == spec.pod_selector
+        let candidates = nsidx.servers.iter().map(|(srvname, srv)| {
+            (
+                srvname.clone(),
+                srv.port().clone(),
+                srv.pod_selector().clone(),
+            )
+        });
+        if let Some(conflicting) =
+            conflicting_server(name, &spec.port, &spec.pod_selector, candidates)
+        {
+            bail!("server selector overlaps with existing server {conflicting}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the name of the first `candidate` (other than `name` itself) whose port and pod
+/// selector may overlap with `port`/`pod_selector`, if any.
+///
+/// Shared by the live-API and index-fallback lookup paths in [`validate_server`] and
+/// [`validate_server_against_index`] so the conflict logic itself can be tested without either a
+/// `kube::Client` or a populated `Index`.
+fn conflicting_server(
+    name: &str,
+    port: &Port,
+    pod_selector: &LabelSelector,
+    candidates: impl IntoIterator<Item = (String, Port, LabelSelector)>,
+) -> Option<String> {
+    candidates
+        .into_iter()
+        .find_map(|(cname, cport, cselector)| {
+            if cname != name
+                && ports_may_conflict(&cport, port)
+                && selectors_may_overlap(&cselector, pod_selector)
             {
-                bail!("identical server spec already exists");
+                Some(cname)
+            } else {
+                None
             }
+        })
+}
+
+/// Returns `true` if `a` and `b` cannot be proven to select disjoint ports.
+///
+/// Numeric ports and names are compared directly; a numeric port and a named port are always
+/// considered to conflict because the name's underlying container port cannot be resolved
+/// without the pod spec, so we conservatively flag it.
+fn ports_may_conflict(a: &Port, b: &Port) -> bool {
+    match (a, b) {
+        (Port::Number(a), Port::Number(b)) => a == b,
+        (Port::Name(a), Port::Name(b)) => a == b,
+        (Port::Number(_), Port::Name(_)) | (Port::Name(_), Port::Number(_)) => true,
+    }
+}
+
+/// Returns `true` unless `a` and `b` can be proven to select disjoint sets of pods.
+///
+/// An empty selector selects all pods in the namespace, so it overlaps with everything. Beyond
+/// that, we only rule out overlap when we can find an explicit contradiction between the two
+/// selectors' requirements on a shared label key; any other pair of selectors is conservatively
+/// treated as potentially overlapping.
+fn selectors_may_overlap(a: &LabelSelector, b: &LabelSelector) -> bool {
+    if is_empty_selector(a) || is_empty_selector(b) {
+        return true;
+    }
+
+    let a_reqs = requirements(a);
+    let b_reqs = requirements(b);
+
+    !a_reqs
+        .iter()
+        .any(|ra| b_reqs.iter().any(|rb| requirements_contradict(ra, rb)))
+}
+
+fn is_empty_selector(selector: &LabelSelector) -> bool {
+    selector
+        .match_labels
+        .as_ref()
+        .map_or(true, |m| m.is_empty())
+        && selector
+            .match_expressions
+            .as_ref()
+            .map_or(true, |e| e.is_empty())
+}
+
+/// A single normalized label requirement on one key: the set of values it requires (for
+/// `In`/equality) or excludes (for `NotIn`), or that the key be present (`Exists`) or absent
+/// (`DoesNotExist`).
+enum Requirement {
+    /// The key must be present and its value must be one of `values`.
+    In {
+        key: String,
+        values: HashSet<String>,
+    },
+    /// The key, if present, must not have a value in `values`.
+    NotIn {
+        key: String,
+        values: HashSet<String>,
+    },
+    /// The key must be present, with any value.
+    Exists { key: String },
+    /// The key must be absent.
+    DoesNotExist { key: String },
+}
+
+fn requirements(selector: &LabelSelector) -> Vec<Requirement> {
+    let mut reqs = Vec::new();
+
+    if let Some(match_labels) = &selector.match_labels {
+        for (key, value) in match_labels {
+            reqs.push(Requirement::In {
+                key: key.clone(),
+                values: std::iter::once(value.clone()).collect(),
+            });
+        }
+    }
+
+    if let Some(match_expressions) = &selector.match_expressions {
+        for LabelSelectorRequirement {
+            key,
+            operator,
+            values,
+        } in match_expressions
+        {
+            let values = || values.clone().unwrap_or_default().into_iter().collect();
+            match operator.as_str() {
+                "In" => reqs.push(Requirement::In {
+                    key: key.clone(),
+                    values: values(),
+                }),
+                "NotIn" => reqs.push(Requirement::NotIn {
+                    key: key.clone(),
+                    values: values(),
+                }),
+                "Exists" => reqs.push(Requirement::Exists { key: key.clone() }),
+                "DoesNotExist" => reqs.push(Requirement::DoesNotExist { key: key.clone() }),
+                _ => {}
+            }
+        }
+    }
+
+    reqs
+}
+
+/// Returns `true` if `a` and `b` constrain the same label key in mutually exclusive ways, i.e.
+/// no pod could satisfy both requirements.
+fn requirements_contradict(a: &Requirement, b: &Requirement) -> bool {
+    use Requirement::*;
+
+    match (a, b) {
+        (
+            In {
+                key: ak,
+                values: av,
+            },
+            In {
+                key: bk,
+                values: bv,
+            },
+        ) => ak == bk && av.is_disjoint(bv),
+        (
+            In {
+                key: ak,
+                values: av,
+            },
+            NotIn {
+                key: bk,
+                values: bv,
+            },
+        )
+        | (
+            NotIn {
+                key: bk,
+                values: bv,
+            },
+            In {
+                key: ak,
+                values: av,
+            },
+        ) => ak == bk && av.is_subset(bv),
+        (Exists { key: ak }, DoesNotExist { key: bk })
+        | (DoesNotExist { key: bk }, Exists { key: ak }) => ak == bk,
+        (In { key: ak, .. }, DoesNotExist { key: bk })
+        | (DoesNotExist { key: bk }, In { key: ak, .. }) => ak == bk,
+        _ => false,
+    }
+}
+
+// === HTTPRoute ===
+
+fn admit_httproute(req: AdmissionRequest) -> AdmissionResponse {
+    let rsp = AdmissionResponse::from(&req);
+    let (ns, name, spec) = match parse_spec::<HttpRouteSpec>(req) {
+        Ok(s) => s,
+        Err(error) => {
+            warn!(%error, "failed to deserialize httproute from admission request");
+            return AdmissionResponse::invalid(error);
+        }
+    };
+
+    match validate_httproute(spec) {
+        Ok(()) => rsp,
+        Err(error) => {
+            info!(%error, %ns, %name, "denying HTTPRoute");
+            rsp.deny(error)
+        }
+    }
+}
+
+fn validate_httproute(spec: HttpRouteSpec) -> Result<()> {
+    for parent_ref in spec.parent_refs.iter().flatten() {
+        if parent_ref.group.as_deref() != Some(&*Server::group(&()))
+            || parent_ref.kind.as_deref() != Some(&*Server::kind(&()))
+        {
+            bail!("parentRefs must reference a policy.linkerd.io Server");
+        }
+    }
+
+    for rule in spec.rules.iter().flatten() {
+        validate_httproute_rule(rule)?;
+    }
+
+    Ok(())
+}
+
+fn validate_httproute_rule(rule: &HttpRouteRule) -> Result<()> {
+    let matches = rule.matches.as_deref().unwrap_or_default();
+    if matches.is_empty() {
+        bail!("HTTPRoute rule must specify at least one match");
+    }
+
+    for (i, m) in matches.iter().enumerate() {
+        if matches[..i].contains(m) {
+            bail!("HTTPRoute rule contains a duplicate match");
         }
+
+        validate_httproute_match(m)?;
     }
 
     Ok(())
 }
 
+fn validate_httproute_match(m: &HttpRouteMatch) -> Result<()> {
+    if let Some(HttpPathMatch::RegularExpression { value }) = &m.path {
+        if let Err(error) = regex::Regex::new(value) {
+            bail!("invalid regular expression path match {value:?}: {error}");
+        }
+    }
+
+    Ok(())
+}
+
+// === Mutation ===
+
+/// Produces a JSON patch of default values to apply to a resource's spec.
+///
+/// Implemented per resource kind and invoked by the mutating webhook; an empty patch means the
+/// spec is left unmodified.
+trait Mutation {
+    fn defaults(&self) -> json_patch::Patch;
+}
+
+impl Mutation for AuthorizationPolicySpec {
+    fn defaults(&self) -> json_patch::Patch {
+        let mut ops = Vec::new();
+
+        if self.target_ref.group.is_none() {
+            ops.push(json_patch::PatchOperation::Add(json_patch::AddOperation {
+                path: "/spec/targetRef/group".to_string(),
+                value: serde_json::Value::String(Server::group(&()).to_string()),
+            }));
+        }
+
+        json_patch::Patch(ops)
+    }
+}
+
+impl Mutation for ServerSpec {
+    fn defaults(&self) -> json_patch::Patch {
+        let mut ops = Vec::new();
+
+        // Normalize named ports to lowercase, matching Kubernetes' own port name conventions.
+        if let Port::Name(name) = &self.port {
+            let normalized = name.to_lowercase();
+            if *name != normalized {
+                ops.push(json_patch::PatchOperation::Replace(
+                    json_patch::ReplaceOperation {
+                        path: "/spec/port".to_string(),
+                        value: serde_json::Value::String(normalized),
+                    },
+                ));
+            }
+        }
+
+        json_patch::Patch(ops)
+    }
+}
+
+impl Mutation for HttpRouteSpec {
+    /// No defaults are filled in for `HTTPRoute` today; it still goes through the shared
+    /// `mutate` path so that all three resource kinds are treated uniformly.
+    fn defaults(&self) -> json_patch::Patch {
+        json_patch::Patch(Vec::new())
+    }
+}
+
+/// Deserializes `req`'s spec as `T` and returns an `AdmissionResponse` carrying `T`'s default
+/// patch, if any. Never denies admission: a resource that fails to deserialize or default is
+/// passed through unmodified, since validation is the validating webhook's job.
+fn mutate<T: Mutation + DeserializeOwned>(req: AdmissionRequest) -> AdmissionResponse {
+    let rsp = AdmissionResponse::from(&req);
+
+    let (ns, name, spec) = match parse_spec::<T>(req) {
+        Ok(s) => s,
+        Err(error) => {
+            warn!(%error, "failed to deserialize resource for defaulting; admitting unmodified");
+            return rsp;
+        }
+    };
+
+    let patch = spec.defaults();
+    if patch.0.is_empty() {
+        return rsp;
+    }
+
+    match rsp.clone().with_patch(patch) {
+        Ok(patched) => patched,
+        Err(error) => {
+            warn!(%error, %ns, %name, "failed to encode default patch; admitting unmodified");
+            rsp
+        }
+    }
+}
+
 // === utils ===
 
 fn parse_spec<T: DeserializeOwned>(req: AdmissionRequest) -> Result<(String, String, T)> {
@@ -202,3 +619,390 @@ fn json_response(rsp: AdmissionReview) -> Result<Response<Body>, Error> {
         .body(Body::from(bytes))
         .expect("admission review response must be valid"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label_selector(
+        match_labels: &[(&str, &str)],
+        match_expressions: &[(&str, &str, &[&str])],
+    ) -> LabelSelector {
+        LabelSelector {
+            match_labels: if match_labels.is_empty() {
+                None
+            } else {
+                Some(
+                    match_labels
+                        .iter()
+                        .map(|(k, v)| (k.to_string(), v.to_string()))
+                        .collect(),
+                )
+            },
+            match_expressions: if match_expressions.is_empty() {
+                None
+            } else {
+                Some(
+                    match_expressions
+                        .iter()
+                        .map(|(key, operator, values)| LabelSelectorRequirement {
+                            key: key.to_string(),
+                            operator: operator.to_string(),
+                            values: Some(values.iter().map(|v| v.to_string()).collect()),
+                        })
+                        .collect(),
+                )
+            },
+        }
+    }
+
+    #[test]
+    fn ports_numeric_match() {
+        assert!(ports_may_conflict(&Port::Number(80), &Port::Number(80)));
+    }
+
+    #[test]
+    fn ports_numeric_mismatch() {
+        assert!(!ports_may_conflict(&Port::Number(80), &Port::Number(8080)));
+    }
+
+    #[test]
+    fn ports_named_match() {
+        assert!(ports_may_conflict(
+            &Port::Name("http".to_string()),
+            &Port::Name("http".to_string())
+        ));
+    }
+
+    #[test]
+    fn ports_named_mismatch() {
+        assert!(!ports_may_conflict(
+            &Port::Name("http".to_string()),
+            &Port::Name("admin".to_string())
+        ));
+    }
+
+    #[test]
+    fn ports_numeric_vs_named_conservatively_conflict() {
+        // We can't resolve a named port without the pod spec, so a numeric port and a named port
+        // are always treated as potentially the same port.
+        assert!(ports_may_conflict(
+            &Port::Number(80),
+            &Port::Name("http".to_string())
+        ));
+        assert!(ports_may_conflict(
+            &Port::Name("http".to_string()),
+            &Port::Number(80)
+        ));
+    }
+
+    #[test]
+    fn empty_selector_overlaps_with_everything() {
+        let empty = label_selector(&[], &[]);
+        let other = label_selector(&[("app", "foo")], &[]);
+        assert!(selectors_may_overlap(&empty, &other));
+        assert!(selectors_may_overlap(&other, &empty));
+        assert!(selectors_may_overlap(&empty, &empty));
+    }
+
+    #[test]
+    fn in_in_disjoint_values_do_not_overlap() {
+        let a = label_selector(&[], &[("env", "In", &["prod"])]);
+        let b = label_selector(&[], &[("env", "In", &["staging"])]);
+        assert!(!selectors_may_overlap(&a, &b));
+    }
+
+    #[test]
+    fn in_in_overlapping_values_may_overlap() {
+        let a = label_selector(&[], &[("env", "In", &["prod", "staging"])]);
+        let b = label_selector(&[], &[("env", "In", &["staging"])]);
+        assert!(selectors_may_overlap(&a, &b));
+    }
+
+    #[test]
+    fn in_notin_subset_does_not_overlap() {
+        // Every value `a` can take is excluded by `b`, so no pod can satisfy both.
+        let a = label_selector(&[], &[("env", "In", &["prod"])]);
+        let b = label_selector(&[], &[("env", "NotIn", &["prod", "staging"])]);
+        assert!(!selectors_may_overlap(&a, &b));
+    }
+
+    #[test]
+    fn in_notin_non_subset_may_overlap() {
+        // `a` can still take a value (`canary`) that `b` doesn't exclude.
+        let a = label_selector(&[], &[("env", "In", &["prod", "canary"])]);
+        let b = label_selector(&[], &[("env", "NotIn", &["prod", "staging"])]);
+        assert!(selectors_may_overlap(&a, &b));
+    }
+
+    #[test]
+    fn exists_does_not_exist_same_key_do_not_overlap() {
+        let a = label_selector(&[], &[("env", "Exists", &[])]);
+        let b = label_selector(&[], &[("env", "DoesNotExist", &[])]);
+        assert!(!selectors_may_overlap(&a, &b));
+    }
+
+    #[test]
+    fn in_does_not_exist_same_key_do_not_overlap() {
+        let a = label_selector(&[], &[("env", "In", &["prod"])]);
+        let b = label_selector(&[], &[("env", "DoesNotExist", &[])]);
+        assert!(!selectors_may_overlap(&a, &b));
+    }
+
+    #[test]
+    fn unrelated_keys_may_overlap() {
+        let a = label_selector(&[("app", "foo")], &[]);
+        let b = label_selector(&[("env", "prod")], &[]);
+        assert!(selectors_may_overlap(&a, &b));
+    }
+
+    fn httproute_spec(value: serde_json::Value) -> HttpRouteSpec {
+        serde_json::from_value(value).expect("must deserialize HTTPRouteSpec")
+    }
+
+    fn httproute_match(value: serde_json::Value) -> HttpRouteMatch {
+        serde_json::from_value(value).expect("must deserialize HTTPRouteMatch")
+    }
+
+    #[test]
+    fn httproute_parent_ref_must_be_a_linkerd_server() {
+        let spec = httproute_spec(serde_json::json!({
+            "parentRefs": [{
+                "group": "gateway.networking.k8s.io",
+                "kind": "Gateway",
+                "name": "my-gateway",
+            }],
+        }));
+        assert!(validate_httproute(spec).is_err());
+    }
+
+    #[test]
+    fn httproute_parent_ref_accepts_a_linkerd_server() {
+        let spec = httproute_spec(serde_json::json!({
+            "parentRefs": [{
+                "group": "policy.linkerd.io",
+                "kind": "Server",
+                "name": "my-server",
+            }],
+        }));
+        assert!(validate_httproute(spec).is_ok());
+    }
+
+    #[test]
+    fn httproute_rule_with_no_matches_is_rejected() {
+        let rule: HttpRouteRule =
+            serde_json::from_value(serde_json::json!({ "matches": [] })).unwrap();
+        assert!(validate_httproute_rule(&rule).is_err());
+    }
+
+    #[test]
+    fn httproute_rule_with_duplicate_matches_is_rejected() {
+        let rule: HttpRouteRule = serde_json::from_value(serde_json::json!({
+            "matches": [
+                { "path": { "type": "Exact", "value": "/foo" } },
+                { "path": { "type": "Exact", "value": "/foo" } },
+            ],
+        }))
+        .unwrap();
+        assert!(validate_httproute_rule(&rule).is_err());
+    }
+
+    #[test]
+    fn httproute_rule_with_distinct_matches_is_accepted() {
+        let rule: HttpRouteRule = serde_json::from_value(serde_json::json!({
+            "matches": [
+                { "path": { "type": "Exact", "value": "/foo" } },
+                { "path": { "type": "Exact", "value": "/bar" } },
+            ],
+        }))
+        .unwrap();
+        assert!(validate_httproute_rule(&rule).is_ok());
+    }
+
+    #[test]
+    fn httproute_invalid_regex_path_match_is_rejected() {
+        let m = httproute_match(serde_json::json!({
+            "path": { "type": "RegularExpression", "value": "(unbalanced" },
+        }));
+        let error = validate_httproute_match(&m).unwrap_err();
+        assert!(
+            error.to_string().contains("(unbalanced"),
+            "error should surface the invalid pattern: {error}"
+        );
+    }
+
+    #[test]
+    fn httproute_valid_regex_path_match_is_accepted() {
+        let m = httproute_match(serde_json::json!({
+            "path": { "type": "RegularExpression", "value": "^/foo/.*$" },
+        }));
+        assert!(validate_httproute_match(&m).is_ok());
+    }
+
+    #[test]
+    fn webhook_mode_routes_validate_and_its_legacy_alias() {
+        assert_eq!(
+            webhook_mode(&http::Method::POST, "/"),
+            Some(WebhookMode::Validate)
+        );
+        assert_eq!(
+            webhook_mode(&http::Method::POST, "/validate"),
+            Some(WebhookMode::Validate)
+        );
+    }
+
+    #[test]
+    fn webhook_mode_routes_mutate() {
+        assert_eq!(
+            webhook_mode(&http::Method::POST, "/mutate"),
+            Some(WebhookMode::Mutate)
+        );
+    }
+
+    #[test]
+    fn webhook_mode_rejects_unknown_paths_and_methods() {
+        assert_eq!(webhook_mode(&http::Method::POST, "/unknown"), None);
+        assert_eq!(webhook_mode(&http::Method::GET, "/validate"), None);
+    }
+
+    fn authz_policy_spec(value: serde_json::Value) -> AuthorizationPolicySpec {
+        serde_json::from_value(value).expect("must deserialize AuthorizationPolicySpec")
+    }
+
+    #[test]
+    fn authz_policy_defaults_fills_in_missing_target_ref_group() {
+        let spec = authz_policy_spec(serde_json::json!({
+            "targetRef": { "kind": "Server", "name": "my-server" },
+            "requiredAuthenticationRefs": [],
+        }));
+        let patch = spec.defaults();
+        assert_eq!(patch.0.len(), 1);
+    }
+
+    #[test]
+    fn authz_policy_defaults_is_a_noop_when_group_is_already_set() {
+        let spec = authz_policy_spec(serde_json::json!({
+            "targetRef": {
+                "group": "policy.linkerd.io",
+                "kind": "Server",
+                "name": "my-server",
+            },
+            "requiredAuthenticationRefs": [],
+        }));
+        let patch = spec.defaults();
+        assert!(patch.0.is_empty());
+    }
+
+    fn server_spec(value: serde_json::Value) -> ServerSpec {
+        serde_json::from_value(value).expect("must deserialize ServerSpec")
+    }
+
+    #[test]
+    fn server_defaults_lowercases_a_mixed_case_port_name() {
+        let spec = server_spec(serde_json::json!({
+            "podSelector": {},
+            "port": "HTTP",
+        }));
+        let patch = spec.defaults();
+        assert_eq!(patch.0.len(), 1);
+    }
+
+    #[test]
+    fn server_defaults_is_a_noop_when_port_name_is_already_lowercase() {
+        let spec = server_spec(serde_json::json!({
+            "podSelector": {},
+            "port": "http",
+        }));
+        let patch = spec.defaults();
+        assert!(patch.0.is_empty());
+    }
+
+    #[test]
+    fn server_defaults_is_a_noop_for_numeric_ports() {
+        let spec = server_spec(serde_json::json!({
+            "podSelector": {},
+            "port": 80,
+        }));
+        let patch = spec.defaults();
+        assert!(patch.0.is_empty());
+    }
+
+    #[test]
+    fn httproute_defaults_is_always_a_noop() {
+        let spec = httproute_spec(serde_json::json!({}));
+        let patch = spec.defaults();
+        assert!(patch.0.is_empty());
+    }
+
+    #[test]
+    fn conflicting_server_ignores_itself() {
+        let candidates = vec![(
+            "web".to_string(),
+            Port::Number(80),
+            label_selector(&[], &[]),
+        )];
+        assert_eq!(
+            conflicting_server(
+                "web",
+                &Port::Number(80),
+                &label_selector(&[], &[]),
+                candidates
+            ),
+            None,
+        );
+    }
+
+    #[test]
+    fn conflicting_server_flags_an_overlapping_port_and_selector() {
+        let candidates = vec![(
+            "other".to_string(),
+            Port::Number(80),
+            label_selector(&[("app", "foo")], &[]),
+        )];
+        assert_eq!(
+            conflicting_server(
+                "web",
+                &Port::Number(80),
+                &label_selector(&[("app", "foo")], &[]),
+                candidates,
+            ),
+            Some("other".to_string()),
+        );
+    }
+
+    #[test]
+    fn conflicting_server_ignores_a_disjoint_selector() {
+        let candidates = vec![(
+            "other".to_string(),
+            Port::Number(80),
+            label_selector(&[], &[("env", "In", &["prod"])]),
+        )];
+        assert_eq!(
+            conflicting_server(
+                "web",
+                &Port::Number(80),
+                &label_selector(&[], &[("env", "In", &["staging"])]),
+                candidates,
+            ),
+            None,
+        );
+    }
+
+    #[test]
+    fn conflicting_server_ignores_a_disjoint_port() {
+        let candidates = vec![(
+            "other".to_string(),
+            Port::Number(80),
+            label_selector(&[("app", "foo")], &[]),
+        )];
+        assert_eq!(
+            conflicting_server(
+                "web",
+                &Port::Number(8080),
+                &label_selector(&[("app", "foo")], &[]),
+                candidates,
+            ),
+            None,
+        );
+    }
+}